@@ -0,0 +1,69 @@
+//! A tiny HTTP server that serves a Prometheus `Registry` at `/metrics`.
+//!
+//! This mirrors the `http_service` module of the upstream libp2p `metrics` example: it is
+//! intentionally minimal (no router, no dependencies beyond what's already in the tree) since
+//! its only job is to let an operator `curl`/scrape the registry instead of grepping logs.
+//! The TCP/IO types come from whichever of async-std or tokio the `runtime-*` feature selects,
+//! matching the runtime the rest of the binary is built with.
+#[cfg(feature = "runtime-async-std")]
+use async_std::io::{ReadExt, WriteExt};
+#[cfg(feature = "runtime-async-std")]
+use async_std::net::{TcpListener, TcpStream};
+use prometheus_client::encoding::text::encode;
+use prometheus_client::registry::Registry;
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "runtime-tokio")]
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+#[cfg(feature = "runtime-tokio")]
+use tokio::net::{TcpListener, TcpStream};
+
+/// Binds `address` and answers every request with the encoded contents of `registry`,
+/// regardless of the requested path or method.
+pub(crate) async fn serve(
+    address: std::net::SocketAddr,
+    registry: Arc<Mutex<Registry>>,
+) -> std::io::Result<()> {
+    let listener = TcpListener::bind(address).await?;
+    // `address` may have used port 0 to request an OS-assigned port, so report the address the
+    // listener actually bound to rather than the (possibly wildcard) one we asked for.
+    let bound_address = listener.local_addr()?;
+    tracing::info!("Metrics available at http://{bound_address}/metrics");
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let registry = Arc::clone(&registry);
+        #[cfg(feature = "runtime-async-std")]
+        async_std::task::spawn(async move {
+            if let Err(err) = handle_connection(stream, registry).await {
+                tracing::debug!("Error serving metrics request: {err}");
+            }
+        });
+        #[cfg(feature = "runtime-tokio")]
+        tokio::task::spawn(async move {
+            if let Err(err) = handle_connection(stream, registry).await {
+                tracing::debug!("Error serving metrics request: {err}");
+            }
+        });
+    }
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    registry: Arc<Mutex<Registry>>,
+) -> std::io::Result<()> {
+    // We don't care about the request itself, only that one arrived; a single read of up to
+    // 1 KiB consumes it (a longer request is not read to its end).
+    let mut buf = [0u8; 1024];
+    let _ = stream.read(&mut buf).await?;
+
+    let mut body = String::new();
+    encode(&mut body, &registry.lock().unwrap()).map_err(std::io::Error::other)?;
+
+    let response = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.flush().await
+}
@@ -2,17 +2,196 @@
 //!
 //! reference: https://docs.rs/libp2p/latest/libp2p/tutorials/ping/index.html
 use futures::prelude::*;
-use libp2p::swarm::SwarmEvent;
-use libp2p::{ping, tcp, tls, yamux, Multiaddr};
+use futures_timer::Delay;
+use libp2p::kad;
+use libp2p::metrics::{Metrics, Recorder};
+use libp2p::swarm::{NetworkBehaviour, SwarmEvent};
+use libp2p::{identify, mdns, ping, tcp, tls, yamux, Multiaddr, PeerId};
+use prometheus_client::registry::Registry;
+use std::collections::HashMap;
 use std::error::Error;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tracing::level_filters::LevelFilter;
 use tracing_subscriber::EnvFilter;
 
+mod http_service;
+
+#[cfg(all(feature = "runtime-async-std", feature = "runtime-tokio"))]
+compile_error!(
+    "`runtime-async-std` and `runtime-tokio` are mutually exclusive; enable exactly one"
+);
+#[cfg(not(any(feature = "runtime-async-std", feature = "runtime-tokio")))]
+compile_error!("enable exactly one of the `runtime-async-std`/`runtime-tokio` features");
+
+/// How often the aggregate RTT summary in [`RttStats`] is logged.
+const RTT_REPORT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The IPFS public bootstrap nodes, used to seed our routing table when no `--bootstrap`
+/// multiaddr is given on the command line.
+const IPFS_BOOTSTRAP_NODES: &[&str] = &[
+    "/dnsaddr/bootstrap.libp2p.io/p2p/QmNnooDu7bfjPFoTZYxMNLWUQJyrVwtbZg5gBMjTezGAJN",
+    "/dnsaddr/bootstrap.libp2p.io/p2p/QmQCU2EcMqAqQPR2i9bChDtGNJchTbq5TbXJJ16u19uLTa",
+    "/dnsaddr/bootstrap.libp2p.io/p2p/QmbLHAnMoJPWSCR5Zhtx6BHJX9KiKNN6tpvbUcqanj75Nb",
+    "/dnsaddr/bootstrap.libp2p.io/p2p/QmcZf59bWwK5XFi76CZX8cbJ4BhTzzA3gU1ZjYZcYW3dwt",
+];
+
+/// Flags that take a value, so their value is never mistaken for the positional dial target.
+const FLAGS_WITH_VALUE: &[&str] = &["--transport", "--bootstrap", "--find-peer", "--metrics-addr"];
+
+/// Parses `--metrics-addr <addr>` out of the given args, falling back to the
+/// `PING_METRICS_ADDR` environment variable and then to `127.0.0.1:0` (an OS-assigned port),
+/// so two instances started on the same machine never fight over the same metrics port.
+fn metrics_addr(args: &[String]) -> Result<std::net::SocketAddr, Box<dyn Error>> {
+    let value = match args.iter().position(|arg| arg == "--metrics-addr") {
+        Some(idx) => args.get(idx + 1).cloned(),
+        None => std::env::var("PING_METRICS_ADDR").ok(),
+    };
+    match value {
+        Some(addr) => Ok(addr.parse()?),
+        None => Ok("127.0.0.1:0".parse().expect("valid socket address")),
+    }
+}
+
+/// Returns the arguments that are not a recognized flag or one of its values - i.e. the
+/// "positional" arguments, which for this binary is just the optional multiaddr to dial.
+fn positional_args(args: &[String]) -> Vec<&String> {
+    let mut positional = Vec::new();
+    let mut skip_next = false;
+    for arg in args {
+        if skip_next {
+            skip_next = false;
+            continue;
+        }
+        if FLAGS_WITH_VALUE.contains(&arg.as_str()) {
+            skip_next = true;
+            continue;
+        }
+        positional.push(arg);
+    }
+    positional
+}
+
+/// Splits a bootstrap multiaddr of the form `.../p2p/<peer-id>` into its `(PeerId, Multiaddr)`
+/// parts, the shape `kad::Behaviour::add_address` expects.
+fn parse_bootstrap_addr(addr: &str) -> Result<(PeerId, Multiaddr), Box<dyn Error>> {
+    let mut multiaddr: Multiaddr = addr.parse()?;
+    match multiaddr.pop() {
+        Some(libp2p::multiaddr::Protocol::P2p(peer_id)) => Ok((peer_id, multiaddr)),
+        _ => Err(format!("bootstrap multiaddr {addr} is missing a /p2p/<peer-id> suffix").into()),
+    }
+}
+
+/// Running min/mean/variance/max of a peer's ping RTTs, updated one sample at a time with
+/// Welford's online algorithm so we never have to keep the full sample history around.
+#[derive(Debug)]
+struct RttStats {
+    count: u64,
+    mean: f64,
+    m2: f64,
+    min: Duration,
+    max: Duration,
+}
+
+impl Default for RttStats {
+    fn default() -> Self {
+        RttStats {
+            count: 0,
+            mean: 0.0,
+            m2: 0.0,
+            min: Duration::MAX,
+            max: Duration::ZERO,
+        }
+    }
+}
+
+impl RttStats {
+    fn update(&mut self, sample: Duration) {
+        self.min = self.min.min(sample);
+        self.max = self.max.max(sample);
+
+        let x = sample.as_secs_f64();
+        self.count += 1;
+        let delta = x - self.mean;
+        self.mean += delta / self.count as f64;
+        self.m2 += delta * (x - self.mean);
+    }
+
+    fn stddev(&self) -> f64 {
+        if self.count == 0 {
+            0.0
+        } else {
+            (self.m2 / self.count as f64).sqrt()
+        }
+    }
+}
+
+/// Which transport stack to build the swarm on top of, selected with `--transport`
+/// (defaults to `tcp` if the flag is absent).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Transport {
+    /// TCP + TLS + Yamux, as described in the tutorial.
+    Tcp,
+    /// QUIC, which provides encrypted streams out-of-the-box without a separate
+    /// multiplexer/security layer.
+    Quic,
+}
+
+impl Transport {
+    /// Parses `--transport <tcp|quic>` out of the given args, falling back to the
+    /// `PING_TRANSPORT` environment variable and then to [`Transport::Tcp`].
+    fn from_args(args: &[String]) -> Result<Self, Box<dyn Error>> {
+        let value = match args.iter().position(|arg| arg == "--transport") {
+            Some(idx) => args.get(idx + 1).cloned(),
+            None => std::env::var("PING_TRANSPORT").ok(),
+        };
+        match value.as_deref() {
+            Some("tcp") | None => Ok(Transport::Tcp),
+            Some("quic") => Ok(Transport::Quic),
+            Some(other) => Err(format!("unknown transport {other:?}, expected tcp or quic").into()),
+        }
+    }
+
+    /// The wildcard multiaddr to listen on for this transport.
+    fn listen_addr(self) -> Multiaddr {
+        match self {
+            Transport::Tcp => "/ip4/0.0.0.0/tcp/0".parse().expect("valid multiaddr"),
+            Transport::Quic => "/ip4/0.0.0.0/udp/0/quic-v1".parse().expect("valid multiaddr"),
+        }
+    }
+}
+
 // https://docs.libp2p.io/concepts/appendix/glossary/ can be handy to read along with this code
 //
-// async_std provides an asynchronous runtime similat to Tokio, but with less features
-#[async_std::main]
+// Combining multiple protocols into a single `NetworkBehaviour` is the idiomatic way to
+// compose functionality in libp2p: the derive macro generates an event enum (`BehaviourEvent` below)
+// that wraps each sub-behaviour's own event type, and dispatches inbound/outbound messages
+// to whichever sub-behaviour owns the relevant protocol.
+//
+// * `ping` tells us how long a round-trip to a peer takes.
+// * `identify` tells us what protocols a peer supports, its agent version, its listen
+//   addresses, and - crucially for NAT traversal - what address it observed us dialing from.
+// * `mdns` discovers other libp2p nodes on the local network so we don't have to pass a
+//   multiaddr by hand every time we want two instances to find each other.
+// * `kad` lets us locate peers that are neither on the local network nor dialed directly, by
+//   walking the Kademlia DHT from a handful of bootstrap nodes.
+#[derive(NetworkBehaviour)]
+struct Behaviour {
+    ping: ping::Behaviour,
+    identify: identify::Behaviour,
+    #[cfg(feature = "runtime-async-std")]
+    mdns: mdns::async_io::Behaviour,
+    #[cfg(feature = "runtime-tokio")]
+    mdns: mdns::tokio::Behaviour,
+    kad: kad::Behaviour<kad::store::MemoryStore>,
+}
+
+// async_std provides an asynchronous runtime similat to Tokio, but with less features.
+// Which one actually drives the swarm is picked at compile time via the mutually exclusive
+// `runtime-tokio`/`runtime-async-std` Cargo features, so this binary can be embedded in a
+// larger tokio application without pulling in a second executor.
+#[cfg_attr(feature = "runtime-async-std", async_std::main)]
+#[cfg_attr(feature = "runtime-tokio", tokio::main)]
 async fn main() -> Result<(), Box<dyn Error>> {
     // it is an utility the for implementing and composing tracing subscribers
     // in this case traces are filtered using the `RUST_LOG` environment variable
@@ -24,33 +203,90 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     tracing::info!("Starting ping program...");
 
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let transport = Transport::from_args(&args)?;
+    tracing::info!("Using {transport:?} transport");
+
+    fn build_behaviour(
+        key: &libp2p::identity::Keypair,
+    ) -> Result<Behaviour, Box<dyn Error + Send + Sync>> {
+        Ok(Behaviour {
+            ping: ping::Behaviour::default(),
+            identify: identify::Behaviour::new(identify::Config::new(
+                "/ping-example/1.0.0".to_string(),
+                key.public(),
+            )),
+            #[cfg(feature = "runtime-async-std")]
+            mdns: mdns::async_io::Behaviour::new(mdns::Config::default(), key.public().to_peer_id())?,
+            #[cfg(feature = "runtime-tokio")]
+            mdns: mdns::tokio::Behaviour::new(mdns::Config::default(), key.public().to_peer_id())?,
+            kad: kad::Behaviour::new(
+                key.public().to_peer_id(),
+                kad::store::MemoryStore::new(key.public().to_peer_id()),
+            ),
+        })
+    }
+
     // Called also "switch", see documentation https://docs.libp2p.io/concepts/multiplex/switch
     // and also `libp2p::swarm` docs. The swarm contains the state of the network as a whole
     //
     // with_new_identity creates a new identity for the
     // local node generating a peer id
-    let mut swarm = libp2p::SwarmBuilder::with_new_identity()
-        .with_async_std()
-        // Next up we need to construct a transport. Each transport in libp2p provides encrypted streams.
-        // E.g. combining TCP to establish connections, TLS to encrypt these connections and Yamux
-        // to run one or more streams on a connection. Another libp2p transport is QUIC,
-        // providing encrypted streams out-of-the-box. We will stick to TCP for now.
-        // Each of these implement the Transport trait.
-        .with_tcp(
-            tcp::Config::default(),
-            tls::Config::new,
-            // the multiplexer protocol used for the tcp connection
-            yamux::Config::default,
-        )?
-        // a `NetworkBehaviour` defines what bytes and to whom to send on the network.
-        .with_behaviour(|_| ping::Behaviour::default())?
-        // Allows us to observe pings for 30 seconds. How long to keep a connection alive once it is idling
-        .with_swarm_config(|cfg| cfg.with_idle_connection_timeout(Duration::from_secs(30)))
-        .build();
+    //
+    // Each transport in libp2p provides encrypted streams. TCP needs to be combined with TLS
+    // (to encrypt the connection) and Yamux (to multiplex one or more streams on it), each of
+    // which implements the Transport trait, whereas QUIC provides encrypted streams
+    // out-of-the-box. `--transport` picks which chain `SwarmBuilder` follows.
+    #[cfg(feature = "runtime-async-std")]
+    let builder = libp2p::SwarmBuilder::with_new_identity().with_async_std();
+    #[cfg(feature = "runtime-tokio")]
+    let builder = libp2p::SwarmBuilder::with_new_identity().with_tokio();
+
+    let mut swarm = match transport {
+        Transport::Tcp => builder
+            .with_tcp(
+                tcp::Config::default(),
+                tls::Config::new,
+                // the multiplexer protocol used for the tcp connection
+                yamux::Config::default,
+            )?
+            // a `NetworkBehaviour` defines what bytes and to whom to send on the network.
+            .with_behaviour(build_behaviour)?
+            // Allows us to observe pings for 30 seconds. How long to keep a connection alive once it is idling
+            .with_swarm_config(|cfg| cfg.with_idle_connection_timeout(Duration::from_secs(30)))
+            .build(),
+        Transport::Quic => builder
+            .with_quic()
+            .with_behaviour(build_behaviour)?
+            .with_swarm_config(|cfg| cfg.with_idle_connection_timeout(Duration::from_secs(30)))
+            .build(),
+    };
 
     // this can be seen with RUST_LOG=debug set
     tracing::debug!("Built libp2p swarm/switch");
 
+    // Register the ping/swarm metric families so the events fed into `metrics.record` below
+    // update RTT histograms, connection establishment/closure counts and error rates.
+    // `Registry::default()` is wrapped so the HTTP task below can read it concurrently with
+    // the event loop writing to it.
+    let mut registry = Registry::default();
+    let metrics = Metrics::new(&mut registry);
+    let registry = Arc::new(Mutex::new(registry));
+
+    // Defaults to an OS-assigned port (`:0`) so that running two instances on the same
+    // machine - the scenario mDNS discovery is built for - doesn't have the second one's
+    // metrics server silently fail to bind to an already-used fixed port.
+    let metrics_address = metrics_addr(&args)?;
+    let serve_metrics = async move {
+        if let Err(err) = http_service::serve(metrics_address, Arc::clone(&registry)).await {
+            tracing::error!("Metrics server on {metrics_address} failed: {err}");
+        }
+    };
+    #[cfg(feature = "runtime-async-std")]
+    async_std::task::spawn(serve_metrics);
+    #[cfg(feature = "runtime-tokio")]
+    tokio::task::spawn(serve_metrics);
+
     // Tell the swarm to listen on all interfaces and a random, OS-assigned port.
     //
     // the P2P network node will bind and listen for incoming connections on
@@ -60,20 +296,80 @@ async fn main() -> Result<(), Box<dyn Error>> {
     //   available IPv4 addresses assigned to the machine (my laptop).
     //   It's a wildcard that indicates any IP address.
     // * Using 0 as a port means that is randomly assigned by the OS.
-    swarm.listen_on("/ip4/0.0.0.0/tcp/0".parse()?)?;
+    swarm.listen_on(transport.listen_addr())?;
 
-    // Dial/Connect to the peer identified by the multi-address
-    // given as the second command-line argument, if any.
-    if let Some(addr) = std::env::args().nth(1) {
+    // Dial/Connect to the peer identified by the multiaddr passed on the command line, if
+    // any. `positional_args` strips out `--transport`/`--bootstrap`/`--find-peer` and their
+    // values first, so a `--bootstrap <multiaddr>` entry is never mistaken for this.
+    if let Some(addr) = positional_args(&args)
+        .into_iter()
+        .find(|arg| arg.parse::<Multiaddr>().is_ok())
+    {
         let remote: Multiaddr = addr.parse()?;
         swarm.dial(remote)?;
         println!("Dialed {addr}")
     }
 
+    // Seed the routing table with either the `--bootstrap <multiaddr>` entries given on the
+    // command line (repeatable) or, absent those, the well-known IPFS public bootstrap nodes.
+    let bootstrap_addrs: Vec<&str> = args
+        .iter()
+        .zip(args.iter().skip(1))
+        .filter(|(flag, _)| *flag == "--bootstrap")
+        .map(|(_, addr)| addr.as_str())
+        .collect();
+    let bootstrap_addrs = if bootstrap_addrs.is_empty() {
+        IPFS_BOOTSTRAP_NODES.to_vec()
+    } else {
+        bootstrap_addrs
+    };
+    for addr in bootstrap_addrs {
+        let (peer_id, addr) = parse_bootstrap_addr(addr)?;
+        swarm.behaviour_mut().kad.add_address(&peer_id, addr);
+    }
+    swarm.behaviour_mut().kad.bootstrap()?;
+
+    // `--find-peer <peer-id>` issues a `get_closest_peers` query; once it completes (see
+    // `kad::Event::OutboundQueryProgressed` below) we dial and ping every peer it turned up.
+    if let Some(idx) = args.iter().position(|arg| arg == "--find-peer") {
+        let target: PeerId = args
+            .get(idx + 1)
+            .ok_or("--find-peer requires a peer id")?
+            .parse()?;
+        swarm.behaviour_mut().kad.get_closest_peers(target);
+    }
+
+    // Per-peer RTT aggregates, reported every `RTT_REPORT_INTERVAL` so users get a latency
+    // profile instead of having to eyeball individual ping lines.
+    let mut rtts: HashMap<PeerId, RttStats> = HashMap::new();
+    let mut report_timer = Delay::new(RTT_REPORT_INTERVAL).fuse();
+
     loop {
-        // Returns a `Future` that resolves when the next item
-        // in this (TCP in this example) stream is ready.
-        match swarm.select_next_some().await {
+        // `select_next_some` is itself a `FusedFuture`, so it can sit directly in `select!`
+        // alongside the report timer without an extra `.fuse()`.
+        let event = futures::select! {
+            event = swarm.select_next_some() => event,
+            _ = report_timer => {
+                for (peer, stats) in &rtts {
+                    tracing::info!(
+                        "RTT summary for {peer}: min={:?} mean={:.2}ms stddev={:.2}ms max={:?} (n={})",
+                        stats.min,
+                        stats.mean * 1000.0,
+                        stats.stddev() * 1000.0,
+                        stats.max,
+                        stats.count,
+                    );
+                }
+                report_timer = Delay::new(RTT_REPORT_INTERVAL).fuse();
+                continue;
+            }
+        };
+        // Feed every event into the registry before handling it: this is what lets an
+        // operator scrape connection counters at `/metrics` instead of grepping through the
+        // `tracing` output below. RTT histograms come from recording the inner `ping::Event`
+        // in the ping arm.
+        metrics.record(&event);
+        match event {
             SwarmEvent::NewListenAddr { address, .. } => tracing::info!("Listening on {address:?}"),
             SwarmEvent::ConnectionEstablished {
                 peer_id,
@@ -88,7 +384,76 @@ async fn main() -> Result<(), Box<dyn Error>> {
             // Once we dial the peer we'll see two events:
             // * there has been a TCP connection to the peer, from another peer (ping)
             // * the local node answers to the other peer (pong)
-            SwarmEvent::Behaviour(event) => tracing::info!("{event:?}"),
+            SwarmEvent::Behaviour(BehaviourEvent::Ping(event)) => {
+                metrics.record(&event);
+                if let Ok(rtt) = event.result {
+                    rtts.entry(event.peer).or_default().update(rtt);
+                }
+                tracing::info!("{event:?}")
+            }
+            // Reported once the identify protocol has exchanged info with a remote peer.
+            // `info.observed_addr` is what the remote believes our external address to be,
+            // which is the piece of information needed to reason about NATs.
+            SwarmEvent::Behaviour(BehaviourEvent::Identify(event)) => {
+                metrics.record(&event);
+                match event {
+                    identify::Event::Received { peer_id, info, .. } => {
+                        tracing::info!("Identified {peer_id:?}: {info:?}")
+                    }
+                    event => tracing::debug!("{event:?}"),
+                }
+            }
+            // A peer showed up on the local network; dial it so the two nodes start pinging
+            // each other without any multiaddr having to be passed on the command line.
+            SwarmEvent::Behaviour(BehaviourEvent::Mdns(mdns::Event::Discovered(peers))) => {
+                for (peer_id, addr) in peers {
+                    // mDNS re-announces every peer on every `query_interval` (5 minutes by
+                    // default), so without this guard two long-running nodes would dial each
+                    // other again on every re-announcement, leaking a new outbound connection
+                    // each time since nothing here caps concurrent connections per peer.
+                    if swarm.is_connected(&peer_id) {
+                        continue;
+                    }
+                    tracing::info!("mDNS discovered peer {peer_id} at {addr}");
+                    // Any other dial error (e.g. already dialing) is an ordinary outcome here
+                    // too, so log and move on instead of aborting the node.
+                    if let Err(err) = swarm.dial(addr) {
+                        tracing::info!("Could not dial {peer_id}: {err}");
+                    }
+                }
+            }
+            SwarmEvent::Behaviour(BehaviourEvent::Mdns(mdns::Event::Expired(peers))) => {
+                for (peer_id, addr) in peers {
+                    tracing::info!("mDNS peer {peer_id} at {addr} expired");
+                }
+            }
+            // The `get_closest_peers` query kicked off by `--find-peer` reports one
+            // `OutboundQueryProgressed` event per incremental improvement of the candidate
+            // set; only the one with `step.last` set is the final result, so we wait for
+            // that before dialing anyone (otherwise we'd redial the same peers on every
+            // partial update).
+            SwarmEvent::Behaviour(BehaviourEvent::Kad(kad::Event::OutboundQueryProgressed {
+                result: kad::QueryResult::GetClosestPeers(result),
+                step,
+                ..
+            })) if step.last => match result {
+                Ok(kad::GetClosestPeersOk { key, peers }) => {
+                    let target = PeerId::from_bytes(&key).ok();
+                    tracing::info!("Found {} peers closest to {target:?}", peers.len());
+                    for peer_id in peers {
+                        // `kad::Behaviour` already recorded each peer's address in its
+                        // routing table while walking the DHT, and supplies it to the swarm
+                        // via `handle_pending_outbound_connection`, so dialing the bare
+                        // `PeerId` is enough. A peer we're already connected/dialing to is an
+                        // entirely ordinary outcome here, so log and move on instead of
+                        // aborting the node.
+                        if let Err(err) = swarm.dial(peer_id) {
+                            tracing::info!("Could not dial {peer_id}: {err}");
+                        }
+                    }
+                }
+                Err(err) => tracing::info!("get_closest_peers failed: {err:?}"),
+            },
             SwarmEvent::ConnectionClosed {
                 peer_id,
                 connection_id,
@@ -96,9 +461,87 @@ async fn main() -> Result<(), Box<dyn Error>> {
                 num_established,
                 cause,
             } => {
+                if num_established == 0 {
+                    rtts.remove(&peer_id);
+                }
                 tracing::info!("Connection closed: {peer_id:?}, {connection_id:?}, {endpoint:?}, {num_established:?}, {cause:?}")
             }
             _ => {}
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rtt_stats_tracks_min_mean_stddev_max() {
+        let mut stats = RttStats::default();
+        for secs in [1, 2, 3] {
+            stats.update(Duration::from_secs(secs));
+        }
+
+        assert_eq!(stats.min, Duration::from_secs(1));
+        assert_eq!(stats.max, Duration::from_secs(3));
+        assert_eq!(stats.mean, 2.0);
+        // Population stddev of {1, 2, 3} is sqrt(2/3).
+        assert!((stats.stddev() - (2.0_f64 / 3.0).sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rtt_stats_stddev_is_zero_with_no_samples() {
+        assert_eq!(RttStats::default().stddev(), 0.0);
+    }
+
+    #[test]
+    fn transport_from_args_defaults_to_tcp() {
+        assert_eq!(Transport::from_args(&[]).unwrap(), Transport::Tcp);
+    }
+
+    #[test]
+    fn transport_from_args_parses_flag() {
+        let args = vec!["--transport".to_string(), "quic".to_string()];
+        assert_eq!(Transport::from_args(&args).unwrap(), Transport::Quic);
+    }
+
+    #[test]
+    fn transport_from_args_rejects_unknown_value() {
+        let args = vec!["--transport".to_string(), "carrier-pigeon".to_string()];
+        assert!(Transport::from_args(&args).is_err());
+    }
+
+    #[test]
+    fn parse_bootstrap_addr_splits_peer_id_from_multiaddr() {
+        let addr = "/dnsaddr/bootstrap.libp2p.io/p2p/QmNnooDu7bfjPFoTZYxMNLWUQJyrVwtbZg5gBMjTezGAJN";
+        let (peer_id, multiaddr) = parse_bootstrap_addr(addr).unwrap();
+
+        assert_eq!(
+            peer_id.to_string(),
+            "QmNnooDu7bfjPFoTZYxMNLWUQJyrVwtbZg5gBMjTezGAJN"
+        );
+        assert_eq!(multiaddr.to_string(), "/dnsaddr/bootstrap.libp2p.io");
+    }
+
+    #[test]
+    fn parse_bootstrap_addr_rejects_missing_peer_id() {
+        assert!(parse_bootstrap_addr("/dnsaddr/bootstrap.libp2p.io").is_err());
+    }
+
+    #[test]
+    fn positional_args_skips_flags_and_their_values() {
+        let args = vec![
+            "--transport".to_string(),
+            "quic".to_string(),
+            "/ip4/1.2.3.4/tcp/4001".to_string(),
+            "--bootstrap".to_string(),
+            "/dnsaddr/bootstrap.libp2p.io/p2p/QmNnooDu7bfjPFoTZYxMNLWUQJyrVwtbZg5gBMjTezGAJN"
+                .to_string(),
+        ];
+
+        assert_eq!(
+            positional_args(&args),
+            vec![&"/ip4/1.2.3.4/tcp/4001".to_string()]
+        );
+    }
+}